@@ -8,7 +8,7 @@
 use std::io::{BufWriter, Write};
 
 use clap::{AppSettings, ErrorKind, IntoApp, Parser, Subcommand};
-use formulae::{obj_types, Object};
+use formulae::{obj_types, schema::Schema, Object};
 use hashbrown::HashMap;
 
 #[derive(Parser)]
@@ -57,6 +57,25 @@ enum Commands {
         #[clap(required = false)]
         path: Option<String>,
     },
+    Dump {
+        #[clap(short, long, required = false, parse(from_os_str))]
+        output: Option<std::path::PathBuf>,
+    },
+    #[clap(setting(AppSettings::ArgRequiredElseHelp))]
+    Assemble {
+        #[clap(short, long, required = true, parse(from_os_str))]
+        source: std::path::PathBuf,
+    },
+    #[clap(setting(AppSettings::ArgRequiredElseHelp))]
+    Validate {
+        #[clap(short, long, required = true, parse(from_os_str))]
+        schema: std::path::PathBuf,
+    },
+    #[clap(setting(AppSettings::ArgRequiredElseHelp))]
+    Merge {
+        #[clap(short, long, required = true, parse(from_os_str))]
+        overlay: std::path::PathBuf,
+    },
 }
 
 fn split_path(mut path: &str) -> Vec<String> {
@@ -120,7 +139,7 @@ fn main() {
         Commands::New {} => {
             let data = HashMap::new();
             BufWriter::new(std::fs::File::create(&args.filename).unwrap())
-                .write(&Object::Root(data).into_bytes())
+                .write(&Object::Root(data).into_bytes_canonical())
                 .unwrap();
         }
         Commands::Add {
@@ -149,17 +168,49 @@ fn main() {
                             .exit()
                     }
                 }
-                obj_types::UINT32 => {
+                obj_types::INT32 => {
+                    if let Some(value) = value {
+                        Object::Int32(value.parse().unwrap())
+                    } else {
+                        app.error(ErrorKind::MissingRequiredArgument, "Value argument missing")
+                            .exit()
+                    }
+                }
+                obj_types::INT64 => {
+                    if let Some(value) = value {
+                        Object::Int64(value.parse().unwrap())
+                    } else {
+                        app.error(ErrorKind::MissingRequiredArgument, "Value argument missing")
+                            .exit()
+                    }
+                }
+                obj_types::SINT32 => {
+                    if let Some(value) = value {
+                        Object::SInt32(value.parse().unwrap())
+                    } else {
+                        app.error(ErrorKind::MissingRequiredArgument, "Value argument missing")
+                            .exit()
+                    }
+                }
+                obj_types::SINT64 => {
+                    if let Some(value) = value {
+                        Object::SInt64(value.parse().unwrap())
+                    } else {
+                        app.error(ErrorKind::MissingRequiredArgument, "Value argument missing")
+                            .exit()
+                    }
+                }
+                obj_types::FLOAT32 => {
                     if let Some(value) = value {
-                        Object::UInt32(value.parse().unwrap())
+                        Object::Float32(value.parse::<f64>().unwrap() as f32)
                     } else {
                         app.error(ErrorKind::MissingRequiredArgument, "Value argument missing")
                             .exit()
                     }
                 }
-                obj_types::UINT64 => {
+                obj_types::FLOAT64 => {
                     if let Some(value) = value {
-                        Object::UInt64(value.parse().unwrap())
+                        Object::Float64(value.parse::<f64>().unwrap())
                     } else {
                         app.error(ErrorKind::MissingRequiredArgument, "Value argument missing")
                             .exit()
@@ -247,7 +298,7 @@ fn main() {
             }
 
             BufWriter::new(std::fs::File::create(&args.filename).unwrap())
-                .write(&contents.into_bytes())
+                .write(&contents.into_bytes_canonical())
                 .unwrap();
         }
         Commands::Set { path, value } => {
@@ -260,8 +311,12 @@ fn main() {
 
             match object {
                 Object::Bool(val) => *val = value.parse().unwrap(),
-                Object::UInt32(val) => *val = value.parse().unwrap(),
-                Object::UInt64(val) => *val = value.parse().unwrap(),
+                Object::Int32(val) => *val = value.parse().unwrap(),
+                Object::Int64(val) => *val = value.parse().unwrap(),
+                Object::SInt32(val) => *val = value.parse().unwrap(),
+                Object::SInt64(val) => *val = value.parse().unwrap(),
+                Object::Float32(val) => *val = value.parse::<f64>().unwrap() as f32,
+                Object::Float64(val) => *val = value.parse::<f64>().unwrap(),
                 Object::String(val) => *val = value.clone(),
                 _ => {
                     app.error(
@@ -275,7 +330,7 @@ fn main() {
             println!("Successfully set value to {:#X?}", object);
 
             BufWriter::new(std::fs::File::create(&args.filename).unwrap())
-                .write(&contents.into_bytes())
+                .write(&contents.into_bytes_canonical())
                 .unwrap();
         }
         Commands::Rename { path, name } => {
@@ -308,7 +363,7 @@ fn main() {
             }
 
             BufWriter::new(std::fs::File::create(&args.filename).unwrap())
-                .write(&contents.into_bytes())
+                .write(&contents.into_bytes_canonical())
                 .unwrap();
 
             println!("Successfully renamed object from {} to {}", old_name, name);
@@ -328,6 +383,69 @@ fn main() {
                 println!("{:#X?}", contents);
             }
         }
+        Commands::Dump { output } => {
+            let contents = std::fs::read(&args.filename).unwrap();
+            let contents = Object::parse_root(&contents).unwrap();
+            let text = contents.to_text();
+
+            if let Some(output) = output {
+                std::fs::write(output, text).unwrap();
+            } else {
+                print!("{}", text);
+            }
+        }
+        Commands::Assemble { source } => {
+            let text = std::fs::read_to_string(source).unwrap();
+            let contents = match Object::from_text(&text) {
+                Ok(v) => v,
+                Err(e) => app.error(ErrorKind::InvalidValue, e).exit(),
+            };
+
+            BufWriter::new(std::fs::File::create(&args.filename).unwrap())
+                .write(&contents.into_bytes_canonical())
+                .unwrap();
+
+            println!("Successfully assembled '{}'", args.filename.display());
+        }
+        Commands::Validate { schema } => {
+            let contents = std::fs::read(&args.filename).unwrap();
+            let contents = Object::parse_root(&contents).unwrap();
+
+            let schema_text = std::fs::read_to_string(schema).unwrap();
+            let schema_object = match Object::from_text(&schema_text) {
+                Ok(v) => v,
+                Err(e) => app.error(ErrorKind::InvalidValue, e).exit(),
+            };
+            let schema = match Schema::from_object(&schema_object) {
+                Ok(v) => v,
+                Err(e) => app.error(ErrorKind::InvalidValue, e).exit(),
+            };
+
+            let errors = formulae::schema::validate(&contents, &schema);
+            if errors.is_empty() {
+                println!("Document is valid");
+            } else {
+                for (path, message) in &errors {
+                    println!("{}: {}", path, message);
+                }
+                std::process::exit(1);
+            }
+        }
+        Commands::Merge { overlay } => {
+            let contents = std::fs::read(&args.filename).unwrap();
+            let mut contents = Object::parse_root(&contents).unwrap();
+
+            let overlay = std::fs::read(overlay).unwrap();
+            let overlay = Object::parse_root(&overlay).unwrap();
+
+            Object::merge(&mut contents, overlay);
+
+            BufWriter::new(std::fs::File::create(&args.filename).unwrap())
+                .write(&contents.into_bytes_canonical())
+                .unwrap();
+
+            println!("Successfully merged overlay into '{}'", args.filename.display());
+        }
     }
 }
 
@@ -17,18 +17,27 @@ use alloc::{
 use hashbrown::HashMap;
 
 pub mod obj_types;
+pub mod schema;
+mod text;
 
 pub const FORMULAE_MAGIC: &str = "formulae";
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum Object {
     Root(HashMap<String, Object>),
     Bool(bool),
     Int32(u32),
     Int64(u64),
+    SInt32(i32),
+    SInt64(i64),
+    Float32(f32),
+    Float64(f64),
     String(String),
     Dictionary(HashMap<String, Object>),
     Array(Vec<Object>),
+    /// A dotted path to another value within the same root, substituted in
+    /// place by [`Object::resolve`].
+    Reference(String),
 }
 
 fn read_bytes<const N: usize>(input: &[u8]) -> Option<([u8; N], &[u8])> {
@@ -73,9 +82,14 @@ impl Object {
             Self::Bool(_) => obj_types::BOOL,
             Self::Int32(_) => obj_types::INT32,
             Self::Int64(_) => obj_types::INT64,
+            Self::SInt32(_) => obj_types::SINT32,
+            Self::SInt64(_) => obj_types::SINT64,
+            Self::Float32(_) => obj_types::FLOAT32,
+            Self::Float64(_) => obj_types::FLOAT64,
             Self::String(_) => obj_types::STR,
             Self::Dictionary(_) => obj_types::DICT,
             Self::Array(_) => obj_types::ARRAY,
+            Self::Reference(_) => obj_types::REF,
             _ => unreachable!(),
         }
     }
@@ -107,6 +121,34 @@ impl Object {
                     Err("Data unexpectedly ended while parsing Int64 object".to_string())
                 }
             }
+            obj_types::SINT32 => {
+                if let Some((bytes, input)) = read_bytes(input) {
+                    Ok(Some((Self::SInt32(i32::from_le_bytes(bytes)), input)))
+                } else {
+                    Err("Data unexpectedly ended while parsing SInt32 object".to_string())
+                }
+            }
+            obj_types::SINT64 => {
+                if let Some((bytes, input)) = read_bytes(input) {
+                    Ok(Some((Self::SInt64(i64::from_le_bytes(bytes)), input)))
+                } else {
+                    Err("Data unexpectedly ended while parsing SInt64 object".to_string())
+                }
+            }
+            obj_types::FLOAT32 => {
+                if let Some((bytes, input)) = read_bytes(input) {
+                    Ok(Some((Self::Float32(f32::from_le_bytes(bytes)), input)))
+                } else {
+                    Err("Data unexpectedly ended while parsing Float32 object".to_string())
+                }
+            }
+            obj_types::FLOAT64 => {
+                if let Some((bytes, input)) = read_bytes(input) {
+                    Ok(Some((Self::Float64(f64::from_le_bytes(bytes)), input)))
+                } else {
+                    Err("Data unexpectedly ended while parsing Float64 object".to_string())
+                }
+            }
             obj_types::STR => {
                 if let Some((s, input)) = read_string(input) {
                     Ok(Some((Self::String(s), input)))
@@ -114,6 +156,13 @@ impl Object {
                     Err("Data unexpectedly ended while parsing String object".to_string())
                 }
             }
+            obj_types::REF => {
+                if let Some((s, input)) = read_string(input) {
+                    Ok(Some((Self::Reference(s), input)))
+                } else {
+                    Err("Data unexpectedly ended while parsing Reference object".to_string())
+                }
+            }
             obj_types::DICT => {
                 let mut map = HashMap::new();
 
@@ -218,7 +267,11 @@ impl Object {
             Object::Bool(value) => bytes.extend_from_slice(&(*value as u8).to_le_bytes()),
             Object::Int32(value) => bytes.extend_from_slice(&value.to_le_bytes()),
             Object::Int64(value) => bytes.extend_from_slice(&value.to_le_bytes()),
-            Object::String(value) => {
+            Object::SInt32(value) => bytes.extend_from_slice(&value.to_le_bytes()),
+            Object::SInt64(value) => bytes.extend_from_slice(&value.to_le_bytes()),
+            Object::Float32(value) => bytes.extend_from_slice(&value.to_le_bytes()),
+            Object::Float64(value) => bytes.extend_from_slice(&value.to_le_bytes()),
+            Object::String(value) | Object::Reference(value) => {
                 bytes.extend_from_slice(&(value.len() as u64).to_le_bytes());
                 bytes.extend_from_slice(value.as_bytes())
             }
@@ -244,4 +297,272 @@ impl Object {
 
         bytes
     }
+
+    /// Serializes this object the same way as [`Object::into_bytes`], except
+    /// dictionary and root entries are emitted sorted by the raw UTF-8 byte
+    /// ordering of their keys rather than `HashMap` iteration order. This
+    /// makes the output a pure function of the document's contents, at the
+    /// cost of an allocation and a sort per dictionary compared to
+    /// [`Object::into_bytes`].
+    pub fn into_bytes_canonical(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        match self {
+            Object::Root(data) => {
+                bytes.extend_from_slice(FORMULAE_MAGIC.as_bytes());
+                write_canonical_entries(&mut bytes, data);
+            }
+            Object::Dictionary(data) => write_canonical_entries(&mut bytes, data),
+            Object::Array(items) => {
+                for object in items {
+                    bytes.push(object.to_obj_type());
+                    bytes.extend_from_slice(&object.into_bytes_canonical());
+                }
+                bytes.push(obj_types::END);
+                bytes.extend_from_slice(&0u16.to_le_bytes());
+            }
+            Object::Bool(_)
+            | Object::Int32(_)
+            | Object::Int64(_)
+            | Object::SInt32(_)
+            | Object::SInt64(_)
+            | Object::Float32(_)
+            | Object::Float64(_)
+            | Object::String(_)
+            | Object::Reference(_) => bytes.extend_from_slice(&self.into_bytes()),
+        }
+
+        bytes
+    }
+
+    /// Renders this object as the human-readable Formulae text format. The
+    /// result is guaranteed to round-trip through [`Object::from_text`] to a
+    /// structurally equivalent [`Object`], NaN floats excepted: a NaN's sign
+    /// and payload bits are not preserved through the `nan` token, and since
+    /// NaN is never equal to itself under `Object`'s `PartialEq`, a
+    /// round-tripped NaN will not compare equal to the original even though
+    /// both are some NaN value. Re-serializing the round-tripped object with
+    /// [`Object::into_bytes_canonical`] (but not the order-dependent
+    /// [`Object::into_bytes`]) is then guaranteed to reproduce the original
+    /// bytes.
+    pub fn to_text(&self) -> String {
+        text::to_text(self)
+    }
+
+    /// Parses the human-readable Formulae text format produced by
+    /// [`Object::to_text`], returning a [`Object::Root`] on success or an
+    /// error message with a line/column on failure.
+    pub fn from_text(input: &str) -> Result<Self, String> {
+        text::from_text(input)
+    }
+
+    /// Merges `overlay` into `base` in place. Keys present only in `base`
+    /// are kept, keys present only in `overlay` are added, and keys present
+    /// in both are merged recursively if both sides are dictionary-like;
+    /// otherwise, and for arrays and scalars, the overlay value replaces the
+    /// base value wholesale.
+    pub fn merge(base: &mut Self, overlay: Self) {
+        let is_dict_overlay = matches!(overlay, Self::Root(_) | Self::Dictionary(_));
+        let is_dict_base = matches!(base, Self::Root(_) | Self::Dictionary(_));
+
+        if !(is_dict_overlay && is_dict_base) {
+            *base = overlay;
+            return;
+        }
+
+        let overlay_data = match overlay {
+            Self::Root(data) | Self::Dictionary(data) => data,
+            _ => unreachable!(),
+        };
+        let base_data = match base {
+            Self::Root(data) | Self::Dictionary(data) => data,
+            _ => unreachable!(),
+        };
+
+        for (key, overlay_value) in overlay_data {
+            let merge_recursively = matches!(
+                (base_data.get(&key), &overlay_value),
+                (Some(Self::Root(_) | Self::Dictionary(_)), Self::Root(_) | Self::Dictionary(_))
+            );
+
+            if merge_recursively {
+                Self::merge(base_data.get_mut(&key).unwrap(), overlay_value);
+            } else {
+                base_data.insert(key, overlay_value);
+            }
+        }
+    }
+
+    /// Replaces every [`Object::Reference`] node in this tree with a deep
+    /// clone of the value at its target path within the same root, erroring
+    /// on a dangling target or a reference cycle.
+    pub fn resolve(&mut self) -> Result<(), String> {
+        let snapshot = self.clone();
+        let mut stack = Vec::new();
+        *self = resolve_value(&snapshot, &snapshot, &mut stack)?;
+        Ok(())
+    }
+}
+
+fn write_canonical_entries(bytes: &mut Vec<u8>, data: &HashMap<String, Object>) {
+    let mut entries: Vec<(&String, &Object)> = data.iter().collect();
+    entries.sort_by(|a, b| a.0.as_bytes().cmp(b.0.as_bytes()));
+
+    for (key, object) in entries {
+        bytes.push(object.to_obj_type());
+        bytes.extend_from_slice(&(key.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(key.as_bytes());
+        bytes.extend_from_slice(&object.into_bytes_canonical());
+    }
+
+    bytes.push(obj_types::END);
+    bytes.extend_from_slice(&0u16.to_le_bytes());
+}
+
+fn lookup_path<'a>(root: &'a Object, path: &str) -> Result<&'a Object, String> {
+    let mut current = root;
+
+    for segment in path.split('.') {
+        current = match current {
+            Object::Root(data) | Object::Dictionary(data) => data
+                .get(segment)
+                .ok_or_else(|| format!("Reference target '{}' not found", path))?,
+            Object::Array(items) => {
+                let index = segment
+                    .parse::<usize>()
+                    .map_err(|_| format!("Invalid array index '{}' in reference '{}'", segment, path))?;
+
+                items
+                    .get(index)
+                    .ok_or_else(|| format!("Reference target '{}' not found", path))?
+            }
+            _ => return Err(format!("Reference target '{}' not found", path)),
+        };
+    }
+
+    Ok(current)
+}
+
+fn resolve_map(
+    root: &Object,
+    data: &HashMap<String, Object>,
+    stack: &mut Vec<String>,
+) -> Result<HashMap<String, Object>, String> {
+    data.iter()
+        .map(|(key, value)| Ok((key.clone(), resolve_value(root, value, stack)?)))
+        .collect()
+}
+
+fn resolve_value(root: &Object, value: &Object, stack: &mut Vec<String>) -> Result<Object, String> {
+    match value {
+        Object::Reference(path) => {
+            if stack.contains(path) {
+                return Err(format!("Reference cycle detected at '{}'", path));
+            }
+
+            stack.push(path.clone());
+            let resolved = resolve_value(root, lookup_path(root, path)?, stack)?;
+            stack.pop();
+
+            Ok(resolved)
+        }
+        Object::Root(data) => Ok(Object::Root(resolve_map(root, data, stack)?)),
+        Object::Dictionary(data) => Ok(Object::Dictionary(resolve_map(root, data, stack)?)),
+        Object::Array(items) => items
+            .iter()
+            .map(|item| resolve_value(root, item, stack))
+            .collect::<Result<_, _>>()
+            .map(Object::Array),
+        other => Ok(other.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canonical_serialization_ignores_insertion_order() {
+        let mut first = HashMap::new();
+        first.insert("b".to_string(), Object::Int32(2));
+        first.insert("a".to_string(), Object::Int32(1));
+        first.insert("c".to_string(), Object::Int32(3));
+
+        let mut second = HashMap::new();
+        second.insert("c".to_string(), Object::Int32(3));
+        second.insert("a".to_string(), Object::Int32(1));
+        second.insert("b".to_string(), Object::Int32(2));
+
+        assert_eq!(
+            Object::Root(first).into_bytes_canonical(),
+            Object::Root(second).into_bytes_canonical()
+        );
+    }
+
+    fn round_trip(object: Object) -> Object {
+        let obj_type = object.to_obj_type();
+        let bytes = object.into_bytes();
+        let (parsed, rest) = Object::parse(obj_type, &bytes).unwrap().unwrap();
+        assert!(rest.is_empty());
+        parsed
+    }
+
+    #[test]
+    fn float_round_trip_bit_exact() {
+        assert!(matches!(round_trip(Object::Float32(f32::NAN)), Object::Float32(v) if v.is_nan()));
+        assert!(matches!(round_trip(Object::Float32(f32::INFINITY)), Object::Float32(v) if v.is_infinite() && v > 0.0));
+        assert!(
+            matches!(round_trip(Object::Float32(f32::NEG_INFINITY)), Object::Float32(v) if v.is_infinite() && v < 0.0)
+        );
+        assert!(matches!(round_trip(Object::Float64(f64::NAN)), Object::Float64(v) if v.is_nan()));
+        assert_eq!(round_trip(Object::Float32(-1.5)), Object::Float32(-1.5));
+        assert_eq!(round_trip(Object::Float64(-1.5)), Object::Float64(-1.5));
+    }
+
+    #[test]
+    fn signed_int_round_trip() {
+        assert_eq!(round_trip(Object::SInt32(-42)), Object::SInt32(-42));
+        assert_eq!(round_trip(Object::SInt64(-42)), Object::SInt64(-42));
+        assert_eq!(round_trip(Object::SInt32(i32::MIN)), Object::SInt32(i32::MIN));
+        assert_eq!(round_trip(Object::SInt64(i64::MIN)), Object::SInt64(i64::MIN));
+    }
+
+    #[test]
+    fn text_round_trip_non_finite_floats() {
+        let mut data = HashMap::new();
+        data.insert("nan32".to_string(), Object::Float32(f32::NAN));
+        data.insert("inf32".to_string(), Object::Float32(f32::INFINITY));
+        data.insert("neg_inf32".to_string(), Object::Float32(f32::NEG_INFINITY));
+        data.insert("nan64".to_string(), Object::Float64(f64::NAN));
+        data.insert("inf64".to_string(), Object::Float64(f64::INFINITY));
+        data.insert("neg_inf64".to_string(), Object::Float64(f64::NEG_INFINITY));
+        let root = Object::Root(data);
+
+        let parsed = Object::from_text(&root.to_text()).unwrap();
+        let Object::Root(data) = parsed else { panic!("expected a Root") };
+
+        assert!(matches!(data.get("nan32"), Some(Object::Float32(v)) if v.is_nan()));
+        assert!(matches!(data.get("inf32"), Some(Object::Float32(v)) if v.is_infinite() && *v > 0.0));
+        assert!(matches!(data.get("neg_inf32"), Some(Object::Float32(v)) if v.is_infinite() && *v < 0.0));
+        assert!(matches!(data.get("nan64"), Some(Object::Float64(v)) if v.is_nan()));
+        assert!(matches!(data.get("inf64"), Some(Object::Float64(v)) if v.is_infinite() && *v > 0.0));
+        assert!(matches!(data.get("neg_inf64"), Some(Object::Float64(v)) if v.is_infinite() && *v < 0.0));
+    }
+
+    #[test]
+    fn text_round_trip_non_identifier_keys() {
+        let mut data = HashMap::new();
+        data.insert("com.example.app".to_string(), Object::Int32(1));
+        data.insert("b key".to_string(), Object::Int32(2));
+        data.insert("aaa".to_string(), Object::Int32(3));
+        let root = Object::Root(data);
+
+        let text = root.to_text();
+        let aaa_pos = text.find("aaa").unwrap();
+        let b_pos = text.find("b key").unwrap();
+        let com_pos = text.find("com.example.app").unwrap();
+        assert!(aaa_pos < b_pos && b_pos < com_pos, "keys must be sorted: {}", text);
+
+        assert_eq!(Object::from_text(&text).unwrap(), root);
+    }
 }
@@ -8,4 +8,10 @@ pub const INT32: u8 = 1;
 pub const INT64: u8 = 2;
 pub const STR: u8 = 3;
 pub const DICT: u8 = 4;
+pub const ARRAY: u8 = 5;
+pub const REF: u8 = 6;
+pub const FLOAT32: u8 = 7;
+pub const FLOAT64: u8 = 8;
+pub const SINT32: u8 = 9;
+pub const SINT64: u8 = 10;
 pub const END: u8 = 0xFF;
@@ -0,0 +1,169 @@
+/*
+ * Copyright (c) VisualDevelopment 2021-2022.
+ * This project is licensed by the Creative Commons Attribution-NoCommercial-NoDerivatives licence.
+ */
+
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use hashbrown::HashMap;
+
+use crate::Object;
+
+/// The expected shape of a Formulae document, mirroring the [`Object`]
+/// variants it can be validated against.
+#[derive(Debug, PartialEq)]
+pub enum Schema {
+    Bool,
+    Int32,
+    Int64,
+    String,
+    /// An array whose elements all match a single, uniform element schema.
+    Array(Box<Schema>),
+    Dictionary {
+        required: HashMap<String, Schema>,
+        optional: HashMap<String, Schema>,
+        allow_extra: bool,
+    },
+}
+
+impl Schema {
+    /// Builds a [`Schema`] from its Formulae representation, e.g. one
+    /// produced by parsing a schema file with [`Object::from_text`].
+    pub fn from_object(object: &Object) -> Result<Self, String> {
+        let data = match object {
+            Object::Root(data) | Object::Dictionary(data) => data,
+            _ => return Err("Schema definition must be a dictionary".to_string()),
+        };
+
+        let kind = match data.get("type") {
+            Some(Object::String(kind)) => kind.as_str(),
+            _ => return Err("Schema definition is missing a 'type' string".to_string()),
+        };
+
+        match kind {
+            "bool" => Ok(Self::Bool),
+            "int32" => Ok(Self::Int32),
+            "int64" => Ok(Self::Int64),
+            "string" => Ok(Self::String),
+            "array" => {
+                let element = data
+                    .get("element")
+                    .ok_or_else(|| "Array schema is missing an 'element' definition".to_string())?;
+
+                Ok(Self::Array(Box::new(Self::from_object(element)?)))
+            }
+            "dict" => {
+                let required = Self::parse_fields(data.get("required"))?;
+                let optional = Self::parse_fields(data.get("optional"))?;
+                let allow_extra = matches!(data.get("allow_extra"), Some(Object::Bool(true)));
+
+                Ok(Self::Dictionary { required, optional, allow_extra })
+            }
+            other => Err(format!("Unknown schema type '{}'", other)),
+        }
+    }
+
+    fn parse_fields(object: Option<&Object>) -> Result<HashMap<String, Schema>, String> {
+        let Some(object) = object else {
+            return Ok(HashMap::new());
+        };
+
+        let data = match object {
+            Object::Root(data) | Object::Dictionary(data) => data,
+            _ => return Err("Expected a dictionary of field schemas".to_string()),
+        };
+
+        data.iter()
+            .map(|(key, value)| Ok((key.clone(), Self::from_object(value)?)))
+            .collect()
+    }
+}
+
+fn escape_segment(segment: &str) -> String {
+    let mut out = String::with_capacity(segment.len());
+    for c in segment.chars() {
+        if c == '.' || c == '\\' {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn push_path(path: &str, segment: &str) -> String {
+    if path.is_empty() {
+        escape_segment(segment)
+    } else {
+        format!("{}.{}", path, escape_segment(segment))
+    }
+}
+
+fn type_name(object: &Object) -> &'static str {
+    match object {
+        Object::Root(_) => "Root",
+        Object::Bool(_) => "Bool",
+        Object::Int32(_) => "Int32",
+        Object::Int64(_) => "Int64",
+        Object::SInt32(_) => "SInt32",
+        Object::SInt64(_) => "SInt64",
+        Object::Float32(_) => "Float32",
+        Object::Float64(_) => "Float64",
+        Object::String(_) => "String",
+        Object::Dictionary(_) => "Dictionary",
+        Object::Array(_) => "Array",
+        Object::Reference(_) => "Reference",
+    }
+}
+
+fn validate_at(object: &Object, schema: &Schema, path: &str, errors: &mut Vec<(String, String)>) {
+    match (schema, object) {
+        (Schema::Bool, Object::Bool(_))
+        | (Schema::Int32, Object::Int32(_))
+        | (Schema::Int64, Object::Int64(_))
+        | (Schema::String, Object::String(_)) => {}
+        (Schema::Array(element), Object::Array(items)) => {
+            for (index, item) in items.iter().enumerate() {
+                validate_at(item, element, &push_path(path, &index.to_string()), errors);
+            }
+        }
+        (Schema::Dictionary { required, optional, allow_extra }, Object::Root(data) | Object::Dictionary(data)) => {
+            for (key, sub_schema) in required {
+                match data.get(key) {
+                    Some(value) => validate_at(value, sub_schema, &push_path(path, key), errors),
+                    None => errors.push((push_path(path, key), format!("Missing required key '{}'", key))),
+                }
+            }
+
+            for (key, value) in data {
+                if required.contains_key(key) {
+                    continue;
+                }
+
+                if let Some(sub_schema) = optional.get(key) {
+                    validate_at(value, sub_schema, &push_path(path, key), errors);
+                } else if !allow_extra {
+                    errors.push((push_path(path, key), format!("Unexpected key '{}'", key)));
+                }
+            }
+        }
+        _ => errors.push((
+            path.to_string(),
+            format!("Expected {:?}, found {}", schema, type_name(object)),
+        )),
+    }
+}
+
+/// Validates `document` against `schema`, returning every violation found
+/// rather than stopping at the first one. Each violation is paired with the
+/// dotted path to the offending node, using the same `\.`-escaping
+/// convention as the CLI's `split_path`.
+pub fn validate(document: &Object, schema: &Schema) -> Vec<(String, String)> {
+    let mut errors = Vec::new();
+    validate_at(document, schema, "", &mut errors);
+    errors
+}
@@ -0,0 +1,398 @@
+/*
+ * Copyright (c) VisualDevelopment 2021-2022.
+ * This project is licensed by the Creative Commons Attribution-NoCommercial-NoDerivatives licence.
+ */
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use hashbrown::HashMap;
+
+use crate::Object;
+
+const INDENT: &str = "    ";
+
+fn escape_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            _ => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn write_indent(out: &mut String, depth: usize) {
+    for _ in 0..depth {
+        out.push_str(INDENT);
+    }
+}
+
+/// Writes a float literal, using the canonical lowercase `nan`/`inf`/`-inf`
+/// tokens for non-finite values so [`Parser::parse_number`] can read them
+/// back without the suffix being swallowed by a greedy identifier.
+fn write_float(out: &mut String, is_nan: bool, is_infinite: bool, is_negative: bool, finite: &str, suffix: &str) {
+    match (is_nan, is_infinite) {
+        (true, _) => out.push_str("nan"),
+        (false, true) => out.push_str(if is_negative { "-inf" } else { "inf" }),
+        (false, false) => out.push_str(finite),
+    }
+    out.push_str(suffix);
+}
+
+fn write_dict_body(out: &mut String, data: &HashMap<String, Object>, depth: usize) {
+    if data.is_empty() {
+        out.push_str("{}");
+        return;
+    }
+
+    out.push_str("{\n");
+    for (key, value) in sorted_entries(data) {
+        write_indent(out, depth + 1);
+        out.push_str(&escape_string(key));
+        out.push_str(": ");
+        write_value(out, value, depth + 1);
+        out.push_str(",\n");
+    }
+    write_indent(out, depth);
+    out.push('}');
+}
+
+/// Returns `data`'s entries sorted by the raw UTF-8 byte ordering of their
+/// keys, so the same document always disassembles to the same text,
+/// mirroring `write_canonical_entries` in `into_bytes_canonical`.
+fn sorted_entries(data: &HashMap<String, Object>) -> Vec<(&String, &Object)> {
+    let mut entries: Vec<(&String, &Object)> = data.iter().collect();
+    entries.sort_by(|a, b| a.0.as_bytes().cmp(b.0.as_bytes()));
+    entries
+}
+
+fn write_value(out: &mut String, object: &Object, depth: usize) {
+    match object {
+        Object::Root(data) => write_dict_body(out, data, depth),
+        Object::Bool(value) => out.push_str(if *value { "true" } else { "false" }),
+        Object::Int32(value) => out.push_str(&format!("{}i32", value)),
+        Object::Int64(value) => out.push_str(&format!("{}i64", value)),
+        Object::SInt32(value) => out.push_str(&format!("{}si32", value)),
+        Object::SInt64(value) => out.push_str(&format!("{}si64", value)),
+        Object::Float32(value) => write_float(
+            out,
+            value.is_nan(),
+            value.is_infinite(),
+            value.is_sign_negative(),
+            &format!("{}", value),
+            "f32",
+        ),
+        Object::Float64(value) => write_float(
+            out,
+            value.is_nan(),
+            value.is_infinite(),
+            value.is_sign_negative(),
+            &format!("{}", value),
+            "f64",
+        ),
+        Object::String(value) => out.push_str(&escape_string(value)),
+        Object::Reference(path) => {
+            out.push('@');
+            out.push_str(&escape_string(path));
+        }
+        Object::Dictionary(data) => write_dict_body(out, data, depth),
+        Object::Array(items) => {
+            if items.is_empty() {
+                out.push_str("[]");
+            } else {
+                out.push_str("[\n");
+                for item in items {
+                    write_indent(out, depth + 1);
+                    write_value(out, item, depth + 1);
+                    out.push_str(",\n");
+                }
+                write_indent(out, depth);
+                out.push(']');
+            }
+        }
+    }
+}
+
+pub(crate) fn to_text(object: &Object) -> String {
+    let data = match object {
+        Object::Root(data) | Object::Dictionary(data) => data,
+        other => {
+            let mut out = String::new();
+            write_value(&mut out, other, 0);
+            return out;
+        }
+    };
+
+    let mut out = String::new();
+    for (key, value) in sorted_entries(data) {
+        out.push_str(&escape_string(key));
+        out.push_str(": ");
+        write_value(&mut out, value, 0);
+        out.push('\n');
+    }
+    out
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+    line: usize,
+    col: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Self { input, pos: 0, line: 1, col: 1 }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn advance(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        if c == '\n' {
+            self.line += 1;
+            self.col = 1;
+        } else {
+            self.col += 1;
+        }
+        Some(c)
+    }
+
+    fn error(&self, message: String) -> String {
+        format!("{} (line {}, column {})", message, self.line, self.col)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(c) if c.is_whitespace()) {
+            self.advance();
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.advance();
+                Ok(())
+            }
+            Some(c) => Err(self.error(format!("Expected '{}' but found '{}'", expected, c))),
+            None => Err(self.error(format!("Expected '{}' but found end of input", expected))),
+        }
+    }
+
+    fn parse_ident(&mut self) -> Result<String, String> {
+        let mut s = String::new();
+        while matches!(self.peek(), Some(c) if c.is_alphanumeric() || c == '_') {
+            s.push(self.advance().unwrap());
+        }
+
+        if s.is_empty() {
+            Err(self.error("Expected an identifier".to_string()))
+        } else {
+            Ok(s)
+        }
+    }
+
+    /// Parses a dictionary key, which is either a quoted string (accepting
+    /// any key the binary format allows, e.g. `"com.example.app"`) or a bare
+    /// identifier for the common case.
+    fn parse_key(&mut self) -> Result<String, String> {
+        if self.peek() == Some('"') {
+            self.parse_string()
+        } else {
+            self.parse_ident()
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+
+        let mut s = String::new();
+        loop {
+            match self.advance() {
+                Some('"') => break Ok(s),
+                Some('\\') => match self.advance() {
+                    Some('"') => s.push('"'),
+                    Some('\\') => s.push('\\'),
+                    Some('n') => s.push('\n'),
+                    Some('r') => s.push('\r'),
+                    Some('t') => s.push('\t'),
+                    Some(c) => break Err(self.error(format!("Unknown escape sequence '\\{}'", c))),
+                    None => break Err(self.error("Unterminated escape sequence".to_string())),
+                },
+                Some(c) => s.push(c),
+                None => break Err(self.error("Unterminated string literal".to_string())),
+            }
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<Object, String> {
+        let mut literal = String::new();
+        if self.peek() == Some('-') {
+            literal.push(self.advance().unwrap());
+        }
+
+        if self.input[self.pos..].starts_with("nan") || self.input[self.pos..].starts_with("inf") {
+            // `nan` / `inf`, recognised by `f32`/`f64`'s `FromStr` impl. Consumed
+            // as exactly three characters so the following type annotation
+            // (e.g. `f32`) isn't swallowed into the same identifier.
+            for _ in 0..3 {
+                literal.push(self.advance().unwrap());
+            }
+        } else {
+            while matches!(self.peek(), Some(c) if c.is_ascii_digit()) {
+                literal.push(self.advance().unwrap());
+            }
+
+            if self.peek() == Some('.') {
+                literal.push(self.advance().unwrap());
+                while matches!(self.peek(), Some(c) if c.is_ascii_digit()) {
+                    literal.push(self.advance().unwrap());
+                }
+            }
+        }
+
+        if literal.is_empty() || literal == "-" {
+            return Err(self.error("Expected a number".to_string()));
+        }
+
+        let annotation = self
+            .parse_ident()
+            .map_err(|_| self.error("Missing type annotation on number literal".to_string()))?;
+
+        match annotation.as_str() {
+            "i32" => literal
+                .parse::<u32>()
+                .map(Object::Int32)
+                .map_err(|e| self.error(format!("Invalid i32 literal: {}", e))),
+            "i64" => literal
+                .parse::<u64>()
+                .map(Object::Int64)
+                .map_err(|e| self.error(format!("Invalid i64 literal: {}", e))),
+            "si32" => literal
+                .parse::<i32>()
+                .map(Object::SInt32)
+                .map_err(|e| self.error(format!("Invalid si32 literal: {}", e))),
+            "si64" => literal
+                .parse::<i64>()
+                .map(Object::SInt64)
+                .map_err(|e| self.error(format!("Invalid si64 literal: {}", e))),
+            "f32" => literal
+                .parse::<f32>()
+                .map(Object::Float32)
+                .map_err(|e| self.error(format!("Invalid f32 literal: {}", e))),
+            "f64" => literal
+                .parse::<f64>()
+                .map(Object::Float64)
+                .map_err(|e| self.error(format!("Invalid f64 literal: {}", e))),
+            other => Err(self.error(format!("Unknown type annotation '{}'", other))),
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Object, String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('"') => Ok(Object::String(self.parse_string()?)),
+            Some('@') => {
+                self.advance();
+                Ok(Object::Reference(self.parse_string()?))
+            }
+            Some('{') => self.parse_dict_value(),
+            Some('[') => self.parse_array(),
+            Some(c)
+                if c.is_ascii_digit()
+                    || c == '-'
+                    || self.input[self.pos..].starts_with("nan")
+                    || self.input[self.pos..].starts_with("inf") =>
+            {
+                self.parse_number()
+            }
+            Some(_) => match self.parse_ident()?.as_str() {
+                "true" => Ok(Object::Bool(true)),
+                "false" => Ok(Object::Bool(false)),
+                other => Err(self.error(format!("Unexpected token '{}'", other))),
+            },
+            None => Err(self.error("Expected a value but found end of input".to_string())),
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<Object, String> {
+        self.expect('[')?;
+
+        let mut items = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.peek() == Some(']') {
+                self.advance();
+                break Ok(Object::Array(items));
+            }
+
+            items.push(self.parse_value()?);
+            self.skip_whitespace();
+
+            match self.peek() {
+                Some(',') => {
+                    self.advance();
+                }
+                Some(']') => {
+                    self.advance();
+                    break Ok(Object::Array(items));
+                }
+                Some(c) => break Err(self.error(format!("Expected ',' or ']' but found '{}'", c))),
+                None => break Err(self.error("Unterminated array".to_string())),
+            }
+        }
+    }
+
+    fn parse_dict_entries(&mut self, terminator: Option<char>) -> Result<HashMap<String, Object>, String> {
+        let mut map = HashMap::new();
+        loop {
+            self.skip_whitespace();
+
+            match (self.peek(), terminator) {
+                (Some(c), Some(term)) if c == term => {
+                    self.advance();
+                    break Ok(map);
+                }
+                (None, None) => break Ok(map),
+                (None, Some(_)) => break Err(self.error("Unterminated dictionary".to_string())),
+                _ => {}
+            }
+
+            let key = self.parse_key()?;
+            self.skip_whitespace();
+            self.expect(':')?;
+            let value = self.parse_value()?;
+            map.insert(key, value);
+
+            self.skip_whitespace();
+            if self.peek() == Some(',') {
+                self.advance();
+            }
+        }
+    }
+
+    fn parse_dict_value(&mut self) -> Result<Object, String> {
+        self.expect('{')?;
+        Ok(Object::Dictionary(self.parse_dict_entries(Some('}'))?))
+    }
+}
+
+pub(crate) fn from_text(input: &str) -> Result<Object, String> {
+    let mut parser = Parser::new(input);
+    let data = parser.parse_dict_entries(None)?;
+    Ok(Object::Root(data))
+}